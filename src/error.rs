@@ -34,6 +34,8 @@ pub enum DeallocationError {
 pub enum ReallocationError {
     #[error(transparent)]
     AllocationError(#[from] AllocationError),
+    #[error("Refusing to move an allocation that must stay in place.")]
+    CannotReallocInPlace,
     #[error(transparent)]
     DeallocationError(#[from] DeallocationError),
     #[error("Deallocation failed, cleanup failed: {0}, {1}")]
@@ -2,7 +2,7 @@
 
 mod error;
 
-use std::alloc::Layout;
+use std::alloc::{GlobalAlloc, Layout, System};
 
 pub use error::*;
 
@@ -10,22 +10,24 @@ pub const HEADER_SIZE: usize = std::mem::size_of::<AllocationHeader>();
 pub const MARKER_FREE: [u8; 8] = *b"Fr33Mmry";
 pub const MARKER_USED: [u8; 8] = *b"U53dMmry";
 
+/// `marker` and `size` alone fit exactly in 16 bytes; `align` doesn't fit in the padding
+/// `#[repr(align(16))]` leaves, so it pushes the rounded-up size to 32 bytes. `HEADER_SIZE`
+/// reflects this doubled per-allocation overhead — it is not still 16 bytes.
 #[repr(align(16))]
 struct AllocationHeader {
     marker: [u8; 8],
     size: usize,
+    align: usize,
 }
 
-/// - A reasonably safe implementation of `alloc`.
-/// - Memory allocated by this function must be freed by this crate's `free`.
-/// - Caller guarantees `free` is called before the returned pointer goes out of scope.
-/// # Errors
-/// - `Err(ArithmeticError)` on integer overflow.
-/// - `Err(ImproperAlignment)` if the global allocator returns a misaligned pointer.
-/// - `Err(LayoutError)` if [`ALIGNMENT`] isn't a power of 2 or the computed size is not aligned.
-/// - `Err(OutOfMemory)` if `alloc()` returns a `nullptr`.
+/// Shared body of [`alloc`] and [`alloc_zeroed`]; `raw_alloc` is the `std::alloc` entry point
+/// used to obtain the underlying block. Returns the user pointer along with the total block
+/// size recorded in its header.
 #[allow(clippy::cast_ptr_alignment)]
-pub fn alloc(size: usize) -> Result<*mut u8, AllocationError> {
+fn alloc_raw(
+    size: usize,
+    raw_alloc: unsafe fn(Layout) -> *mut u8,
+) -> Result<(*mut u8, usize), AllocationError> {
     let size = size
         .checked_add(HEADER_SIZE)
         .ok_or(AllocationError::ArithmeticError)?
@@ -34,13 +36,13 @@ pub fn alloc(size: usize) -> Result<*mut u8, AllocationError> {
 
     let layout = Layout::from_size_align(size, HEADER_SIZE)?;
 
-    let ptr = unsafe { std::alloc::alloc(layout) };
+    let ptr = unsafe { raw_alloc(layout) };
 
     if ptr.is_null() {
         return Err(AllocationError::OutOfMemory);
     }
 
-    if 0 != (ptr as usize % HEADER_SIZE) {
+    if !(ptr as usize).is_multiple_of(HEADER_SIZE) {
         unsafe { std::alloc::dealloc(ptr, layout) };
 
         return Err(AllocationError::ImproperAlignment);
@@ -50,36 +52,123 @@ pub fn alloc(size: usize) -> Result<*mut u8, AllocationError> {
 
     header.marker = MARKER_USED;
     header.size = size;
+    header.align = HEADER_SIZE;
 
     let ptr = unsafe { ptr.add(HEADER_SIZE) };
 
+    Ok((ptr, size))
+}
+
+/// - A reasonably safe implementation of `alloc`.
+/// - Memory allocated by this function must be freed by this crate's `free`.
+/// - Caller guarantees `free` is called before the returned pointer goes out of scope.
+/// # Errors
+/// - `Err(ArithmeticError)` on integer overflow.
+/// - `Err(ImproperAlignment)` if the global allocator returns a misaligned pointer.
+/// - `Err(LayoutError)` if [`HEADER_SIZE`] isn't a power of 2 or the computed size is not aligned.
+/// - `Err(OutOfMemory)` if `alloc()` returns a `nullptr`.
+pub fn alloc(size: usize) -> Result<*mut u8, AllocationError> {
+    alloc_raw(size, std::alloc::alloc).map(|(ptr, _)| ptr)
+}
+
+/// - Like [`alloc`], but the user-visible bytes are guaranteed to be zero.
+/// - Uses `std::alloc::alloc_zeroed` for the underlying block instead of `std::alloc::alloc`.
+/// - The header region itself is always overwritten, so only the bytes past [`HEADER_SIZE`]
+///   need to be zero; since `alloc` rounds the block up to a multiple of [`HEADER_SIZE`], the
+///   trailing padding past the requested `size` ends up zeroed too.
+/// # Errors
+/// Same as [`alloc`].
+pub fn alloc_zeroed(size: usize) -> Result<*mut u8, AllocationError> {
+    alloc_raw(size, std::alloc::alloc_zeroed).map(|(ptr, _)| ptr)
+}
+
+/// Like [`alloc`], but also returns the real usable length of the allocation, which can be
+/// larger than `size` since `alloc` rounds the block up to a multiple of [`HEADER_SIZE`].
+/// Callers can grow into this slack without calling [`relloc`].
+/// # Errors
+/// Same as [`alloc`].
+pub fn alloc_with_size(size: usize) -> Result<(*mut u8, usize), AllocationError> {
+    let (ptr, total_size) = alloc_raw(size, std::alloc::alloc)?;
+
+    Ok((ptr, total_size - HEADER_SIZE))
+}
+
+/// Shared body of [`alloc_aligned`] and [`PsAlloc`]'s `alloc`/`realloc`; `raw_alloc`/`raw_dealloc`
+/// obtain (and, on failure, unwind) the underlying block: the `std::alloc` entry points for
+/// [`alloc_aligned`], [`System`]'s for [`PsAlloc`].
+#[allow(clippy::cast_ptr_alignment)]
+fn alloc_aligned_with(
+    size: usize,
+    align: usize,
+    raw_alloc: unsafe fn(Layout) -> *mut u8,
+    raw_dealloc: unsafe fn(*mut u8, Layout),
+) -> Result<*mut u8, AllocationError> {
+    if align < HEADER_SIZE || !align.is_power_of_two() {
+        return Err(AllocationError::ImproperAlignment);
+    }
+
+    let raw_size = size
+        .checked_add(align)
+        .ok_or(AllocationError::ArithmeticError)?;
+
+    let layout = Layout::from_size_align(raw_size, align)?;
+
+    let raw_ptr = unsafe { raw_alloc(layout) };
+
+    if raw_ptr.is_null() {
+        return Err(AllocationError::OutOfMemory);
+    }
+
+    if !(raw_ptr as usize).is_multiple_of(align) {
+        unsafe { raw_dealloc(raw_ptr, layout) };
+
+        return Err(AllocationError::ImproperAlignment);
+    }
+
+    let ptr = unsafe { raw_ptr.add(align) };
+    let header = unsafe { &mut *(ptr.sub(HEADER_SIZE).cast::<AllocationHeader>()) };
+
+    header.marker = MARKER_USED;
+    header.size = raw_size;
+    header.align = align;
+
     Ok(ptr)
 }
 
-/// - A reasonably safe implementation of `free`.
-/// - This function will free a pointer allocated by `alloc`.
-/// - Caller guarantees that the provided pointer was allocated by this crate's `alloc` function.
-/// - Providing `NULL` is safe and will return `Err(DeallocationError::NullPtr)`.
-/// - Providing any other pointer causes undefined behaviour.
+/// - Allocates `size` bytes whose address is a multiple of `align`, which must be a power of
+///   two no smaller than [`HEADER_SIZE`]. Memory allocated by this function must be freed by
+///   this crate's [`free`].
+/// - The underlying block is `size + align` bytes; the header is placed in the last
+///   [`HEADER_SIZE`] bytes of the leading `align`-byte region, immediately before the
+///   returned pointer, so [`free`]/[`relloc`] can locate it exactly as they do for [`alloc`].
 /// # Errors
-/// - Returns `Err(DeallocationError)` if a safety check fails.
-pub fn free<T>(ptr: *mut T) -> Result<(), DeallocationError> {
+/// - `Err(ArithmeticError)` on integer overflow.
+/// - `Err(ImproperAlignment)` if `align` isn't a power of 2, is smaller than [`HEADER_SIZE`],
+///   or if the global allocator returns a misaligned pointer.
+/// - `Err(LayoutError)` if the computed layout is invalid.
+/// - `Err(OutOfMemory)` if the global allocator returns a `nullptr`.
+pub fn alloc_aligned(size: usize, align: usize) -> Result<*mut u8, AllocationError> {
+    alloc_aligned_with(size, align, std::alloc::alloc, std::alloc::dealloc)
+}
+
+/// Validates `ptr` the way [`free`] always has, and returns a pointer to its header.
+#[allow(clippy::cast_ptr_alignment)]
+fn free_header<T>(ptr: *mut T) -> Result<*mut AllocationHeader, DeallocationError> {
     if ptr.is_null() {
         return Err(DeallocationError::NullPtr);
     }
 
-    if 0 != ptr as usize % HEADER_SIZE {
+    if !(ptr as usize).is_multiple_of(HEADER_SIZE) {
         return Err(DeallocationError::ImproperAlignment);
     }
 
-    #[allow(clippy::cast_ptr_alignment)]
     let header_ptr = unsafe { ptr.cast::<u8>().sub(HEADER_SIZE).cast::<AllocationHeader>() };
 
     if !header_ptr.is_aligned() {
         return Err(DeallocationError::ImproperAlignment);
     }
 
-    let header = unsafe { &mut *header_ptr };
+    let header = unsafe { &*header_ptr };
 
     if header.marker == MARKER_FREE {
         return Err(DeallocationError::DoubleFree);
@@ -87,15 +176,102 @@ pub fn free<T>(ptr: *mut T) -> Result<(), DeallocationError> {
         return Err(DeallocationError::InvalidAllocation);
     }
 
-    let layout = Layout::from_size_align(header.size, HEADER_SIZE)?;
+    Ok(header_ptr)
+}
+
+/// Shared body of [`free`] and [`PsAlloc`]'s `dealloc`/`realloc`; `raw_dealloc` releases the
+/// underlying block: `std::alloc::dealloc` for [`free`], [`System`]'s for [`PsAlloc`].
+fn free_with<T>(
+    ptr: *mut T,
+    raw_dealloc: unsafe fn(*mut u8, Layout),
+) -> Result<(), DeallocationError> {
+    let header_ptr = free_header(ptr)?;
+    let header = unsafe { &mut *header_ptr };
+
+    let layout = Layout::from_size_align(header.size, header.align)?;
+    let raw_ptr = unsafe { ptr.cast::<u8>().sub(header.align) };
 
     header.marker = MARKER_FREE;
 
-    unsafe { std::alloc::dealloc(header_ptr.cast(), layout) };
+    unsafe { raw_dealloc(raw_ptr, layout) };
 
     Ok(())
 }
 
+/// - A reasonably safe implementation of `free`.
+/// - This function will free a pointer allocated by `alloc`.
+/// - Caller guarantees that the provided pointer was allocated by this crate's `alloc` function.
+/// - Providing `NULL` is safe and will return `Err(DeallocationError::NullPtr)`.
+/// - Providing any other pointer causes undefined behaviour.
+/// # Errors
+/// - Returns `Err(DeallocationError)` if a safety check fails.
+pub fn free<T>(ptr: *mut T) -> Result<(), DeallocationError> {
+    free_with(ptr, std::alloc::dealloc)
+}
+
+/// Returns the number of bytes actually usable by the caller in an allocation made by
+/// [`alloc`], [`alloc_zeroed`], [`alloc_aligned`], or [`alloc_with_size`] — this can be more
+/// than was originally requested, since allocating rounds the block up to a multiple of
+/// [`HEADER_SIZE`]. The header and any alignment padding occupy `header.align` bytes ahead of
+/// the user pointer, not always [`HEADER_SIZE`] (see [`alloc_aligned`]).
+/// # Errors
+/// Same validation as [`free`]: `NullPtr`, `ImproperAlignment`, `DoubleFree`,
+/// `InvalidAllocation`.
+pub fn usable_size<T>(ptr: *mut T) -> Result<usize, DeallocationError> {
+    let header_ptr = free_header(ptr)?;
+    let header = unsafe { &*header_ptr };
+
+    Ok(header.size - header.align)
+}
+
+/// Validates `ptr` the way [`relloc`] always has, and returns a pointer to its header.
+#[allow(clippy::cast_ptr_alignment)]
+fn relloc_header(ptr: *mut u8) -> Result<*mut AllocationHeader, ReallocationError> {
+    if ptr.is_null() {
+        return Err(ReallocationError::from(DeallocationError::NullPtr));
+    }
+
+    if !(ptr as usize).is_multiple_of(HEADER_SIZE) {
+        return Err(ReallocationError::ImproperAlignment);
+    }
+
+    let header_ptr = unsafe { ptr.sub(HEADER_SIZE) }.cast::<AllocationHeader>();
+
+    if !header_ptr.is_aligned() {
+        return Err(ReallocationError::ImproperAlignment);
+    }
+
+    let header = unsafe { &*header_ptr };
+
+    if header.marker == MARKER_FREE {
+        return Err(ReallocationError::UseAfterFree);
+    } else if header.marker != MARKER_USED {
+        return Err(ReallocationError::InvalidPointer);
+    }
+
+    Ok(header_ptr)
+}
+
+/// The total block size used to serve `new_size` bytes at the given `align`: a multiple of
+/// [`HEADER_SIZE`] for the `align == HEADER_SIZE` case (mirroring `alloc`'s rounding), or the
+/// exact `new_size + align` that `alloc_aligned` uses for any larger alignment. Blocks that
+/// `alloc_aligned` makes at exactly `HEADER_SIZE` alignment (including those [`relloc`]
+/// relocates) are not rounded, so their size equals this result only when
+/// `new_size + HEADER_SIZE` is already a multiple of [`HEADER_SIZE`].
+fn rounded_block_size(new_size: usize, align: usize) -> Result<usize, AllocationError> {
+    if align == HEADER_SIZE {
+        new_size
+            .checked_add(HEADER_SIZE)
+            .ok_or(AllocationError::ArithmeticError)?
+            .checked_next_multiple_of(HEADER_SIZE)
+            .ok_or(AllocationError::ArithmeticError)
+    } else {
+        new_size
+            .checked_add(align)
+            .ok_or(AllocationError::ArithmeticError)
+    }
+}
+
 /// Reallocates memory allocated by [`alloc`].
 /// # Errors
 /// - `AllocationError` if `alloc()` fails
@@ -117,29 +293,22 @@ pub fn relloc(ptr: *mut u8, new_size: usize) -> Result<*mut u8, ReallocationErro
         return Ok(alloc(new_size)?);
     }
 
-    if 0 != ptr as usize % HEADER_SIZE {
-        return Err(ReallocationError::ImproperAlignment);
-    }
-
-    #[allow(clippy::cast_ptr_alignment)]
-    let header_ptr = unsafe { ptr.sub(HEADER_SIZE) }.cast::<AllocationHeader>();
-
-    if !header_ptr.is_aligned() {
-        return Err(ReallocationError::ImproperAlignment);
-    }
-
+    let header_ptr = relloc_header(ptr)?;
     let header = unsafe { &*header_ptr };
 
-    if header.marker == MARKER_FREE {
-        return Err(ReallocationError::UseAfterFree);
-    } else if header.marker != MARKER_USED {
-        return Err(ReallocationError::InvalidPointer);
+    let rounded = rounded_block_size(new_size, header.align);
+
+    if rounded.is_ok_and(|rounded| rounded == header.size) {
+        return Ok(ptr);
     }
 
-    let new_ptr = alloc(new_size)?;
+    // Relocate at the pointer's original alignment, not just `HEADER_SIZE`, so an
+    // over-aligned block (e.g. from `alloc_aligned`) doesn't come back under-aligned.
+    let new_ptr = alloc_aligned(new_size, header.align)?;
+    let old_usable = header.size - header.align;
 
     unsafe {
-        std::ptr::copy_nonoverlapping::<u8>(ptr, new_ptr, header.size.min(new_size));
+        std::ptr::copy_nonoverlapping::<u8>(ptr, new_ptr, old_usable.min(new_size));
     }
 
     let free_result = free(ptr);
@@ -152,3 +321,124 @@ pub fn relloc(ptr: *mut u8, new_size: usize) -> Result<*mut u8, ReallocationErro
         },
     }
 }
+
+/// Whether [`grow`]/[`shrink`] may relocate the allocation to a new address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReallocPlacement {
+    /// The allocation may move if it doesn't already fit in place.
+    MayMove,
+    /// The allocation must stay at its current address, or the call fails.
+    InPlace,
+}
+
+/// Grows the allocation at `ptr` to hold at least `new_size` bytes.
+///
+/// If the existing block's rounded capacity already covers `new_size`, `ptr` is returned
+/// unchanged with no copy. Otherwise, with [`ReallocPlacement::MayMove`] this behaves like
+/// [`relloc`]; with [`ReallocPlacement::InPlace`] it fails instead of relocating.
+/// # Errors
+/// Same as [`relloc`], plus `CannotReallocInPlace` if `placement` is
+/// [`ReallocPlacement::InPlace`] and the existing allocation is too small.
+pub fn grow(
+    ptr: *mut u8,
+    new_size: usize,
+    placement: ReallocPlacement,
+) -> Result<*mut u8, ReallocationError> {
+    let header_ptr = relloc_header(ptr)?;
+    let header = unsafe { &*header_ptr };
+
+    if new_size <= header.size - header.align {
+        return Ok(ptr);
+    }
+
+    if placement == ReallocPlacement::InPlace {
+        return Err(ReallocationError::CannotReallocInPlace);
+    }
+
+    relloc(ptr, new_size)
+}
+
+/// Shrinks the allocation at `ptr` down to `new_size` bytes.
+///
+/// If `new_size` still fits in the existing block's rounded capacity, `ptr` is returned
+/// unchanged with no copy. Otherwise, with [`ReallocPlacement::MayMove`] this behaves like
+/// [`relloc`]; with [`ReallocPlacement::InPlace`] it fails instead of relocating.
+/// # Errors
+/// Same as [`relloc`], plus `CannotReallocInPlace` if `placement` is
+/// [`ReallocPlacement::InPlace`] and `new_size` no longer fits in the existing block.
+pub fn shrink(
+    ptr: *mut u8,
+    new_size: usize,
+    placement: ReallocPlacement,
+) -> Result<*mut u8, ReallocationError> {
+    let header_ptr = relloc_header(ptr)?;
+    let header = unsafe { &*header_ptr };
+
+    if new_size <= header.size - header.align {
+        return Ok(ptr);
+    }
+
+    if placement == ReallocPlacement::InPlace {
+        return Err(ReallocationError::CannotReallocInPlace);
+    }
+
+    relloc(ptr, new_size)
+}
+
+// `std::alloc::alloc`/`dealloc` dispatch to whatever is registered as the process's
+// `#[global_allocator]`. Once `PsAlloc` is that allocator, calling through those free
+// functions from its own `GlobalAlloc` impl recurses straight back into itself. `System`
+// is a concrete type that always talks to the OS allocator directly, so `PsAlloc` backs
+// onto it instead of onto `alloc_aligned`/`free`'s own `std::alloc::{alloc, dealloc}`.
+unsafe fn system_alloc(layout: Layout) -> *mut u8 {
+    unsafe { System.alloc(layout) }
+}
+
+unsafe fn system_dealloc(ptr: *mut u8, layout: Layout) {
+    unsafe { System.dealloc(ptr, layout) };
+}
+
+/// A zero-sized type implementing [`GlobalAlloc`] on top of this crate's allocation header
+/// bookkeeping, backed by [`System`] rather than the [`alloc_aligned`]/[`free`] free
+/// functions (which would recurse back into `PsAlloc` once it's installed as the process's
+/// `#[global_allocator]`). Dropping `PsAlloc` in as a `#[global_allocator]` gets a process
+/// the double-free / use-after-free / invalid-pointer detection this crate already
+/// performs, while still honouring whatever alignment `Layout` asks for.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PsAlloc;
+
+unsafe impl GlobalAlloc for PsAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let align = layout.align().max(HEADER_SIZE);
+
+        alloc_aligned_with(layout.size(), align, system_alloc, system_dealloc)
+            .unwrap_or(std::ptr::null_mut())
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+        // `free_with`'s own marker checks are the only line of defense here: the trait
+        // contract makes misuse UB regardless, so there is nothing safe to fall back to.
+        let _ = free_with(ptr, system_dealloc);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // `relloc` allocates and frees through `std::alloc::{alloc, dealloc}`, which would
+        // recurse back into `PsAlloc`, so the move is done by hand here on top of `System`.
+        // `usable_size` only reads header memory, so it's safe to reuse as-is.
+        let Ok(old_usable) = usable_size(ptr) else {
+            return std::ptr::null_mut();
+        };
+
+        let align = layout.align().max(HEADER_SIZE);
+
+        let Ok(new_ptr) = alloc_aligned_with(new_size, align, system_alloc, system_dealloc) else {
+            return std::ptr::null_mut();
+        };
+
+        unsafe { std::ptr::copy_nonoverlapping(ptr, new_ptr, old_usable.min(new_size)) };
+
+        let _ = free_with(ptr, system_dealloc);
+
+        new_ptr
+    }
+}